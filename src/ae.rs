@@ -0,0 +1,134 @@
+use CDC;
+use std::default::Default;
+
+/// Default window width used by `AE`
+///
+/// The expected average chunk size produced by AE is roughly `e * w`
+/// (`e` being Euler's number), so this default gives an average chunk
+/// size in the same ballpark as `gear`'s and `bup`'s default
+/// `CHUNK_SIZE` of 8KiB.
+pub const WINDOW_SIZE: usize = 3014;
+
+/// Asymmetric Extremum (AE) content defined chunker
+///
+/// Unlike `Gear`, `Bup` or `Rabin`, AE needs no rolling hash: a
+/// boundary is declared whenever no byte seen in the last `w` bytes
+/// has exceeded the maximum of the current chunk, which is both
+/// cheaper to compute and gives a lower chunk-size variance than a
+/// plain rolling-hash cut. See "AE: An Asymmetric Extremum Content
+/// Defined Chunking Algorithm for Fast and Bandwidth-Efficient Data
+/// Deduplication" (Zhang et al., INFOCOM'15).
+pub struct AE {
+    w: usize,
+    max_value: u8,
+    max_pos: usize,
+    pos: usize,
+}
+
+impl Default for AE {
+    fn default() -> Self {
+        AE::new_with_window(WINDOW_SIZE)
+    }
+}
+
+impl AE {
+    /// Create a new AE engine with the default window width
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a new AE engine with a custom window width `w`
+    ///
+    /// The expected average chunk size is roughly `e * w`.
+    pub fn new_with_window(w: usize) -> Self {
+        AE {
+            w: w,
+            max_value: 0,
+            max_pos: 0,
+            pos: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.max_value = 0;
+        self.max_pos = 0;
+        self.pos = 0;
+    }
+}
+
+impl CDC for AE {
+    fn find_chunk<'a>(&mut self, buf: &'a [u8]) -> Option<(&'a [u8], &'a [u8])> {
+        for (i, &b) in buf.iter().enumerate() {
+            let pos = self.pos;
+            if b <= self.max_value {
+                if pos - self.max_pos == self.w {
+                    self.reset();
+                    return Some((&buf[..i + 1], &buf[i + 1..]));
+                }
+            } else {
+                self.max_value = b;
+                self.max_pos = pos;
+            }
+            self.pos += 1;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AE;
+    use CDC;
+
+    #[test]
+    fn boundary_state_survives_across_buffers() {
+        // A ramp that keeps setting a new max every 6 bytes, so no
+        // boundary is possible until it plateaus at 255 (around byte
+        // 1530) and then holds flat for a full window (64) more. The
+        // first 1500 bytes never see a boundary, so the continuation
+        // after a `None` on the *same* `AE` instance is what actually
+        // finds it, exercising the max_value/max_pos carry-over this
+        // test is meant to check.
+        let data: Vec<u8> = (0..2048).map(|i| ::std::cmp::min(255, i / 6) as u8).collect();
+
+        let mut whole = AE::new_with_window(64);
+        let mut split = AE::new_with_window(64);
+
+        let whole_chunk = whole.find_chunk(&data).map(|(c, _)| c.len());
+
+        let (first_half, second_half) = data.split_at(1500);
+        let split_chunk = match split.find_chunk(first_half) {
+            Some((c, _)) => Some(c.len()),
+            None => {
+                let total = first_half.len();
+                split.find_chunk(second_half).map(|(c, _)| total + c.len())
+            }
+        };
+
+        assert_eq!(whole_chunk, split_chunk);
+    }
+
+    #[cfg(feature = "bench")]
+    mod bench {
+        use test::Bencher;
+        use super::*;
+        use CDC;
+
+        use tests::test_data_1mb;
+
+        #[bench]
+        fn perf_1mb(b: &mut Bencher) {
+            let v = test_data_1mb();
+            b.bytes = v.len() as u64;
+
+            b.iter(|| {
+                let mut cdc = AE::new();
+                let mut buf = v.as_slice();
+
+                while let Some((_last, rest)) = cdc.find_chunk(buf) {
+                    buf = rest;
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use {RollingHash, CDC};
+use gear::Gear;
+
+/// Generate a mask with exactly `ones` one-bits
+///
+/// This follows the approach described in the FastCDC paper: starting
+/// from an empty mask, repeatedly set the lowest bit and rotate by a
+/// pseudo-random amount, until the desired number of one-bits is
+/// reached. Since each step sets at most one new bit, and rotating
+/// never changes the popcount, the popcount grows monotonically and
+/// the loop is guaranteed to hit `ones` exactly.
+fn gen_mask(ones: u32) -> u64 {
+    let mut v: u64 = 0;
+    let mut mask: u64 = 0;
+    while mask.count_ones() != ones {
+        v = v.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        mask = (mask | 1).rotate_left(v as u32 & 0x3f);
+    }
+    mask
+}
+
+/// Derive the `(mask_short, mask_long)` pair used by `FastCDC` for a
+/// given `avg_size` and normalization level.
+fn gen_masks(avg_size: u32, nc_level: u32) -> (u64, u64) {
+    let bits = (avg_size.next_power_of_two() - 1).count_ones();
+    assert!(nc_level <= bits);
+    if bits == 13 && nc_level == 2 {
+        // Constants from the FastCDC paper for the common 8KiB
+        // average chunk size, `nc_level == 2` case.
+        (0x0003590703530000, 0x0000d90003530000)
+    } else {
+        (gen_mask(bits + nc_level), gen_mask(bits - nc_level))
+    }
+}
+
+/// FastCDC chunking engine
+///
+/// Implements the normalized chunking scheme described in the FastCDC
+/// paper (Xia et al., ATC'16) on top of the `Gear` rolling hash. Two
+/// masks of different strictness are derived from `avg_size` and
+/// `nc_level`: a stricter `mask_short` is tested below `avg_size`, and a
+/// looser `mask_long` is tested above it, which pulls the chunk-size
+/// distribution much tighter around `avg_size` than a plain Gear/Bup
+/// cut does. `min_size` bytes are always skipped (cut-point skipping)
+/// and a boundary is forced at `max_size`.
+pub struct FastCDC {
+    gear: Gear,
+    min_size: u32,
+    avg_size: u32,
+    max_size: u32,
+    mask_short: u64,
+    mask_long: u64,
+    current_chunk_size: u32,
+}
+
+impl FastCDC {
+    /// Create a new FastCDC engine with the given size bounds
+    ///
+    /// `nc_level` controls the normalization strength: `0` disables
+    /// normalization (degenerating to a single-mask Gear-style cut),
+    /// while higher levels tighten the chunk-size distribution around
+    /// `avg_size`; the work done per byte is the same at every level.
+    pub fn new(min_size: u32, avg_size: u32, max_size: u32, nc_level: u32) -> Self {
+        let (mask_short, mask_long) = gen_masks(avg_size, nc_level);
+        FastCDC {
+            gear: Gear::new(),
+            min_size: min_size,
+            avg_size: avg_size,
+            max_size: max_size,
+            mask_short: mask_short,
+            mask_long: mask_long,
+            current_chunk_size: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.gear.reset();
+        self.current_chunk_size = 0;
+    }
+}
+
+impl CDC for FastCDC {
+    fn find_chunk<'a>(&mut self, buf: &'a [u8]) -> Option<(&'a [u8], &'a [u8])> {
+        for (i, &b) in buf.iter().enumerate() {
+            self.gear.roll_byte(b);
+            self.current_chunk_size += 1;
+
+            if self.current_chunk_size >= self.max_size {
+                self.reset();
+                return Some((&buf[..i + 1], &buf[i + 1..]));
+            }
+
+            if self.current_chunk_size < self.min_size {
+                continue;
+            }
+
+            let digest = self.gear.digest();
+            let mask = if self.current_chunk_size < self.avg_size {
+                self.mask_short
+            } else {
+                self.mask_long
+            };
+
+            if digest & mask == 0 {
+                self.reset();
+                return Some((&buf[..i + 1], &buf[i + 1..]));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FastCDC, gen_mask};
+    use CDC;
+
+    #[test]
+    fn gen_mask_has_requested_popcount() {
+        for ones in 5..20 {
+            assert_eq!(gen_mask(ones).count_ones(), ones);
+        }
+    }
+
+    #[test]
+    fn never_produces_a_chunk_smaller_than_min_size() {
+        let min_size = 256;
+        let mut cdc = FastCDC::new(min_size, 1024, 4096, 2);
+        let buf = vec![0u8; 1024 * 64];
+        let mut rest = buf.as_slice();
+
+        while let Some((chunk, tail)) = cdc.find_chunk(rest) {
+            assert!(chunk.len() >= min_size as usize || tail.is_empty());
+            rest = tail;
+        }
+    }
+
+    #[cfg(feature = "bench")]
+    mod bench {
+        use test::Bencher;
+        use super::*;
+        use CDC;
+
+        use tests::test_data_1mb;
+
+        #[bench]
+        fn perf_1mb_008k_chunks(b: &mut Bencher) {
+            let v = test_data_1mb();
+            b.bytes = v.len() as u64;
+
+            b.iter(|| {
+                let mut cdc = FastCDC::new(2 * 1024, 8 * 1024, 32 * 1024, 2);
+                let mut buf = v.as_slice();
+
+                while let Some((_last, rest)) = cdc.find_chunk(buf) {
+                    buf = rest;
+                }
+            });
+        }
+    }
+}
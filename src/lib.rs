@@ -0,0 +1,80 @@
+//! # rsroll
+//!
+//! Rolling hash / Content Defined Chunking (CDC)
+//!
+//! This crate implements a number of different rolling hash / CDC
+//! algorithms (`bup`'s rollsum, `gear`, `ae`, `fastcdc`, `rabin`)
+//! behind common traits, so they can be compared and swapped for one
+//! another. `stream` adapts any `CDC` engine to work over
+//! `std::io::Read` instead of an in-memory buffer, and `stats`
+//! collects chunk-size statistics useful for tuning one.
+
+#![cfg_attr(feature = "bench", feature(test))]
+
+#[cfg(feature = "bench")]
+extern crate test;
+
+pub mod ae;
+pub mod bup;
+pub mod fastcdc;
+pub mod gear;
+pub mod rabin;
+pub mod stats;
+pub mod stream;
+
+/// Rolling hash
+///
+/// Implementors of this trait maintain a digest that is updated one
+/// byte at a time, as bytes enter (and, depending on the algorithm,
+/// leave) a sliding window.
+pub trait RollingHash {
+    type Digest;
+
+    /// Roll over one byte of data
+    fn roll_byte(&mut self, byte: u8);
+
+    /// Roll over a slice of data
+    fn roll(&mut self, buf: &[u8]) {
+        for &b in buf {
+            self.roll_byte(b);
+        }
+    }
+
+    /// Return current digest
+    fn digest(&self) -> Self::Digest;
+
+    /// Reset the internal state to the one of a freshly created engine
+    fn reset(&mut self);
+}
+
+/// Content Defined Chunking
+///
+/// Implementors of this trait are able to find chunk edges in a byte
+/// buffer. The state required to do so (eg. a `RollingHash` digest)
+/// persists across calls, so that feeding the same data split across
+/// multiple buffers produces the same edges as feeding it in one go.
+pub trait CDC {
+    /// Find the first chunk edge in `buf`.
+    ///
+    /// Returns `Some((chunk, rest))` if an edge was found in `buf`,
+    /// with `chunk` being the part of `buf` up to (and including) the
+    /// edge, and `rest` being everything after it. Returns `None` if
+    /// no edge was found, in which case all of `buf` was consumed and
+    /// the internal state was updated accordingly.
+    fn find_chunk<'a>(&mut self, buf: &'a [u8]) -> Option<(&'a [u8], &'a [u8])>;
+}
+
+#[cfg(test)]
+mod tests {
+    /// Generate a deterministic pseudo-random 1MB buffer, used by the
+    /// benchmarks of the various engines.
+    pub fn test_data_1mb() -> Vec<u8> {
+        let mut v = vec![0u8; 1024 * 1024];
+        let mut a: u32 = 1;
+        for x in v.iter_mut() {
+            a = a.wrapping_mul(1103515245).wrapping_add(12345);
+            *x = (a >> 16) as u8;
+        }
+        v
+    }
+}
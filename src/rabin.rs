@@ -0,0 +1,208 @@
+use {RollingHash, CDC};
+use std::default::Default;
+
+const WINDOW_SIZE: usize = 64;
+
+/// Degree of the irreducible polynomial used by `Rabin`
+const DEGREE: u32 = 53;
+
+/// Irreducible polynomial of degree `DEGREE` (leading `x^DEGREE` term
+/// included, as bit `DEGREE`), used by `Rabin` as its fingerprinting modulus. This is
+/// the same polynomial used by a number of other Rabin-fingerprint
+/// based chunkers.
+const POLY: u64 = 0x3DA3358B4DC173;
+
+/// Mask keeping only the low `DEGREE` bits of the fingerprint register
+const FP_MASK: u64 = (1u64 << DEGREE) - 1;
+
+/// Default chunk size used by `rabin`
+pub const CHUNK_SIZE: u32 = 1 << CHUNK_BITS;
+
+/// Default chunk size used by `rabin` (log2)
+pub const CHUNK_BITS: u32 = 13;
+
+/// Reduce `x` (a polynomial of degree at most `deg + 7`) modulo the
+/// polynomial of degree `deg` whose lower bits are `poly`.
+fn gf2_reduce(mut x: u128, poly: u64, deg: u32) -> u64 {
+    let modpoly = (1u128 << deg) | poly as u128;
+    let mut i = deg + 7;
+    loop {
+        if (x >> i) & 1 == 1 {
+            x ^= modpoly << (i - deg);
+        }
+        if i == deg {
+            break;
+        }
+        i -= 1;
+    }
+    x as u64
+}
+
+/// Build the `T_mod` table: `T_mod[k]` is `(k << DEGREE) mod POLY`,
+/// used to fold the 8 bits that overflow the fingerprint register back
+/// in after every byte shifted in.
+fn build_t_mod() -> [u64; 256] {
+    let mut t = [0u64; 256];
+    for (k, slot) in t.iter_mut().enumerate() {
+        *slot = gf2_reduce((k as u128) << DEGREE, POLY, DEGREE);
+    }
+    t
+}
+
+/// Build the `T_out` table: `T_out[k]` is the contribution byte value
+/// `k` makes to the fingerprint after `WINDOW_SIZE` further bytes have
+/// been shifted in, i.e. what needs to be subtracted (XORed) out of
+/// the fingerprint once `k` slides out of the window.
+fn build_t_out(t_mod: &[u64; 256]) -> [u64; 256] {
+    let mut t = [0u64; 256];
+    for (k, slot) in t.iter_mut().enumerate() {
+        let mut fp = k as u64;
+        for _ in 0..WINDOW_SIZE {
+            fp = ((fp << 8) ^ t_mod[(fp >> (DEGREE - 8)) as usize]) & FP_MASK;
+        }
+        *slot = fp;
+    }
+    t
+}
+
+/// Rabin fingerprint rolling hash
+///
+/// A polynomial fingerprint over GF(2), using a fixed irreducible
+/// polynomial as its modulus. This is the classic CDC baseline that
+/// `gear` and `bup`'s rollsum are often compared against.
+pub struct Rabin {
+    fp: u64,
+    window: [u8; WINDOW_SIZE],
+    wofs: usize,
+    chunk_bits: u32,
+    t_mod: Box<[u64; 256]>,
+    t_out: Box<[u64; 256]>,
+}
+
+impl Default for Rabin {
+    fn default() -> Self {
+        let t_mod = build_t_mod();
+        let t_out = build_t_out(&t_mod);
+        Rabin {
+            fp: 0,
+            window: [0; WINDOW_SIZE],
+            wofs: 0,
+            chunk_bits: CHUNK_BITS,
+            t_mod: Box::new(t_mod),
+            t_out: Box::new(t_out),
+        }
+    }
+}
+
+impl RollingHash for Rabin {
+    type Digest = u64;
+
+    fn roll_byte(&mut self, new: u8) {
+        let out = unsafe { *self.window.get_unchecked(self.wofs) };
+        unsafe { *self.window.get_unchecked_mut(self.wofs) = new };
+        self.wofs = (self.wofs + 1) % WINDOW_SIZE;
+
+        self.fp = (((self.fp << 8) | new as u64) ^ self.t_mod[(self.fp >> (DEGREE - 8)) as usize]) & FP_MASK;
+        self.fp ^= self.t_out[out as usize];
+    }
+
+    fn digest(&self) -> u64 {
+        self.fp
+    }
+
+    fn reset(&mut self) {
+        // Keep the (expensive to rebuild) tables and `chunk_bits`
+        // around instead of going through `Default`, so resetting at
+        // every chunk edge doesn't recompute them from scratch.
+        self.fp = 0;
+        self.window = [0; WINDOW_SIZE];
+        self.wofs = 0;
+    }
+}
+
+impl Rabin {
+    /// Create new Rabin engine with default chunking settings
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create new Rabin engine with custom chunking settings
+    ///
+    /// `chunk_bits` is number of bits that need to match in
+    /// the edge condition. `CHUNK_BITS` constant is the default.
+    pub fn new_with_chunk_bits(chunk_bits: u32) -> Self {
+        assert!(chunk_bits < 32);
+        Rabin {
+            chunk_bits: chunk_bits,
+            .. Default::default()
+        }
+    }
+}
+
+impl CDC for Rabin {
+    fn find_chunk<'a>(&mut self, buf: &'a [u8]) -> Option<(&'a [u8], &'a [u8])> {
+        let chunk_mask = (1u64 << self.chunk_bits) - 1;
+        for (i, &b) in buf.iter().enumerate() {
+            self.roll_byte(b);
+
+            if self.digest() & chunk_mask == 0 {
+                self.reset();
+                return Some((&buf[..i + 1], &buf[i + 1..]));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rabin;
+    use {RollingHash};
+
+    #[test]
+    fn effective_window_size() {
+        let ones = vec![0x1; 1024];
+        let zeroes = vec![0x0; 1024];
+
+        let mut rabin = Rabin::new();
+        rabin.roll(&ones);
+        let digest = rabin.digest();
+
+        let mut rabin = Rabin::new();
+        rabin.roll(&zeroes);
+
+        for (i, &b) in ones.iter().enumerate() {
+            rabin.roll_byte(b);
+            if rabin.digest() == digest {
+                assert_eq!(i, 63);
+                return;
+            }
+        }
+
+        panic!("matching digest not found");
+    }
+
+    #[cfg(feature = "bench")]
+    mod bench {
+        use test::Bencher;
+        use super::*;
+        use CDC;
+
+        use tests::test_data_1mb;
+
+        #[bench]
+        fn perf_1mb_008k_chunks(b: &mut Bencher) {
+            let v = test_data_1mb();
+            b.bytes = v.len() as u64;
+
+            b.iter(|| {
+                let mut cdc = Rabin::new_with_chunk_bits(13);
+                let mut buf = v.as_slice();
+
+                while let Some((_last, rest)) = cdc.find_chunk(buf) {
+                    buf = rest;
+                }
+            });
+        }
+    }
+}
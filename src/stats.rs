@@ -0,0 +1,189 @@
+use std::io::{self, Read};
+use CDC;
+use stream::Chunker;
+
+/// Chunk-size statistics collected from running a `CDC` engine over
+/// some input
+///
+/// Computed online using Welford's algorithm, so the whole input
+/// never needs to be held in memory just to compute these numbers.
+/// Useful for empirically comparing chunkers, e.g. when tuning
+/// `chunk_bits`, FastCDC's `nc_level`, or AE's window width.
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkStats {
+    count: u64,
+    total_bytes: u64,
+    mean: f64,
+    m2: f64,
+    min: u64,
+    max: u64,
+}
+
+impl ChunkStats {
+    fn new() -> Self {
+        ChunkStats {
+            count: 0,
+            total_bytes: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: u64::max_value(),
+            max: 0,
+        }
+    }
+
+    fn observe(&mut self, len: usize) {
+        let len = len as u64;
+        self.count += 1;
+        self.total_bytes += len;
+
+        let x = len as f64;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        let new_delta = x - self.mean;
+        self.m2 += delta * new_delta;
+
+        if len < self.min {
+            self.min = len;
+        }
+        if len > self.max {
+            self.max = len;
+        }
+    }
+
+    /// Number of chunks observed
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Total number of bytes observed, across all chunks
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// Mean chunk size
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Sample variance of the chunk size (`n - 1` denominator)
+    pub fn variance(&self) -> f64 {
+        if self.count < 2 {
+            0.0
+        } else {
+            self.m2 / (self.count - 1) as f64
+        }
+    }
+
+    /// Standard deviation of the chunk size
+    pub fn stddev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Smallest chunk observed
+    pub fn min(&self) -> u64 {
+        if self.count == 0 {
+            0
+        } else {
+            self.min
+        }
+    }
+
+    /// Largest chunk observed
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+}
+
+/// Run `cdc` over `buf`, collecting chunk-size statistics
+pub fn stats_from_buf<C: CDC>(mut cdc: C, buf: &[u8]) -> ChunkStats {
+    let mut stats = ChunkStats::new();
+    let mut rest = buf;
+    while let Some((chunk, tail)) = cdc.find_chunk(rest) {
+        stats.observe(chunk.len());
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        stats.observe(rest.len());
+    }
+    stats
+}
+
+/// Run `cdc` over everything read from `reader`, collecting
+/// chunk-size statistics
+pub fn stats_from_reader<R: Read, C: CDC>(reader: R, cdc: C) -> io::Result<ChunkStats> {
+    let mut stats = ChunkStats::new();
+    for chunk in Chunker::new(reader, cdc) {
+        stats.observe(chunk?.len());
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stats_from_buf, stats_from_reader, ChunkStats};
+    use gear::Gear;
+
+    #[test]
+    fn stats_match_manual_count() {
+        let mut data = vec![0u8; 128 * 1024];
+        for (i, b) in data.iter_mut().enumerate() {
+            *b = (i * 2654435761) as u8;
+        }
+
+        let stats = stats_from_buf(Gear::new_with_chunk_bits(10), &data);
+
+        assert_eq!(stats.total_bytes(), data.len() as u64);
+        assert!(stats.count() > 0);
+        assert!(stats.min() <= stats.mean() as u64 + 1);
+        assert!(stats.max() >= stats.mean() as u64);
+        assert!(stats.stddev() >= 0.0);
+    }
+
+    #[test]
+    fn welford_matches_manually_computed_moments() {
+        // A fixed, short sequence of chunk lengths with an easily
+        // hand-computed mean and (sample) variance, so a broken
+        // `observe()` (e.g. a wrong `m2` update) doesn't silently pass
+        // the loose bound checks in `stats_match_manual_count`.
+        let lens = [10u64, 20, 30, 40, 100];
+
+        let mut stats = ChunkStats::new();
+        for &len in &lens {
+            stats.observe(len as usize);
+        }
+
+        let n = lens.len() as f64;
+        let sum: u64 = lens.iter().sum();
+        let mean = sum as f64 / n;
+        let variance = lens.iter()
+            .map(|&l| (l as f64 - mean) * (l as f64 - mean))
+            .sum::<f64>() / (n - 1.0);
+
+        assert_eq!(stats.count(), lens.len() as u64);
+        assert_eq!(stats.total_bytes(), sum);
+        assert_eq!(stats.min(), 10);
+        assert_eq!(stats.max(), 100);
+        assert!((stats.mean() - mean).abs() < 1e-9);
+        assert!((stats.variance() - variance).abs() < 1e-9);
+        assert!((stats.stddev() - variance.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stats_from_reader_matches_stats_from_buf() {
+        let mut data = vec![0u8; 128 * 1024];
+        for (i, b) in data.iter_mut().enumerate() {
+            *b = (i * 2654435761) as u8;
+        }
+
+        let buf_stats = stats_from_buf(Gear::new_with_chunk_bits(10), &data);
+        let reader_stats =
+            stats_from_reader(data.as_slice(), Gear::new_with_chunk_bits(10)).unwrap();
+
+        assert_eq!(buf_stats.count(), reader_stats.count());
+        assert_eq!(buf_stats.total_bytes(), reader_stats.total_bytes());
+        assert_eq!(buf_stats.min(), reader_stats.min());
+        assert_eq!(buf_stats.max(), reader_stats.max());
+        assert!((buf_stats.mean() - reader_stats.mean()).abs() < 1e-9);
+        assert!((buf_stats.variance() - reader_stats.variance()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,171 @@
+use std::io::{self, Read};
+use std::mem;
+use CDC;
+
+/// Default size of the blocks read from the underlying reader
+pub const BLOCK_SIZE: usize = 64 * 1024;
+
+/// Streaming adapter turning any `CDC` engine into an `Iterator` over
+/// `std::io::Read`
+///
+/// `CDC::find_chunk` only works on in-memory slices, which forces
+/// callers to hold the whole input in RAM. `Chunker` instead pulls
+/// fixed-size blocks from a reader into an internal buffer and
+/// repeatedly calls `find_chunk` across block boundaries; because the
+/// rolling hash state persists across calls, the boundaries found are
+/// identical to the ones `find_chunk` would find on the whole input at
+/// once. Complete chunks are yielded as owned `Vec<u8>`, and the final
+/// (possibly partial) chunk is flushed once the reader is exhausted.
+pub struct Chunker<R, C> {
+    reader: R,
+    cdc: C,
+    block_size: usize,
+    buf: Vec<u8>,
+    /// Number of leading bytes of `buf` already rolled into `cdc`
+    /// without finding a boundary; only the bytes after this point are
+    /// handed to `find_chunk` on the next call, so no byte is ever
+    /// re-fed into the rolling hash.
+    scanned: usize,
+    eof: bool,
+}
+
+impl<R: Read, C: CDC> Chunker<R, C> {
+    /// Create a new chunker reading from `reader` and using `cdc` to
+    /// find chunk boundaries, reading `BLOCK_SIZE` bytes at a time
+    pub fn new(reader: R, cdc: C) -> Self {
+        Chunker::new_with_block_size(reader, cdc, BLOCK_SIZE)
+    }
+
+    /// Create a new chunker, reading `block_size` bytes from `reader`
+    /// at a time
+    pub fn new_with_block_size(reader: R, cdc: C, block_size: usize) -> Self {
+        Chunker {
+            reader: reader,
+            cdc: cdc,
+            block_size: block_size,
+            buf: Vec::new(),
+            scanned: 0,
+            eof: false,
+        }
+    }
+}
+
+impl<R: Read, C: CDC> Iterator for Chunker<R, C> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.scanned < self.buf.len() {
+                let found_len = self.cdc
+                    .find_chunk(&self.buf[self.scanned..])
+                    .map(|(chunk, _)| chunk.len());
+                match found_len {
+                    Some(len) => {
+                        let chunk: Vec<u8> = self.buf.drain(..self.scanned + len).collect();
+                        self.scanned = 0;
+                        return Some(Ok(chunk));
+                    }
+                    None => {
+                        self.scanned = self.buf.len();
+                    }
+                }
+            }
+
+            if self.eof {
+                if self.buf.is_empty() {
+                    return None;
+                }
+                self.scanned = 0;
+                return Some(Ok(mem::replace(&mut self.buf, Vec::new())));
+            }
+
+            let old_len = self.buf.len();
+            self.buf.resize(old_len + self.block_size, 0);
+            match self.reader.read(&mut self.buf[old_len..]) {
+                Ok(0) => {
+                    self.buf.truncate(old_len);
+                    self.eof = true;
+                }
+                Ok(n) => {
+                    self.buf.truncate(old_len + n);
+                }
+                Err(e) => {
+                    self.buf.truncate(old_len);
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use super::Chunker;
+    use gear::Gear;
+    use CDC;
+
+    #[test]
+    fn matches_in_memory_chunking() {
+        let mut data = vec![0u8; 256 * 1024];
+        for (i, b) in data.iter_mut().enumerate() {
+            *b = (i * 2654435761) as u8;
+        }
+
+        let expected = {
+            let mut chunks = Vec::new();
+            let mut cdc = Gear::new_with_chunk_bits(10);
+            let mut rest = data.as_slice();
+            while let Some((chunk, tail)) = cdc.find_chunk(rest) {
+                chunks.push(chunk.to_vec());
+                rest = tail;
+            }
+            if !rest.is_empty() {
+                chunks.push(rest.to_vec());
+            }
+            chunks
+        };
+
+        let found: io::Result<Vec<Vec<u8>>> = Chunker::new_with_block_size(
+            data.as_slice(),
+            Gear::new_with_chunk_bits(10),
+            4096,
+        ).collect();
+
+        assert_eq!(expected, found.unwrap());
+    }
+
+    #[test]
+    fn matches_in_memory_chunking_with_small_blocks() {
+        // `chunk_bits(10)` gives an average chunk size of ~1KiB, well
+        // above this 32-byte block size, so most calls to `find_chunk`
+        // span several blocks and have to return `None` at least once
+        // before a boundary is found.
+        let mut data = vec![0u8; 64 * 1024];
+        for (i, b) in data.iter_mut().enumerate() {
+            *b = (i * 2654435761) as u8;
+        }
+
+        let expected = {
+            let mut chunks = Vec::new();
+            let mut cdc = Gear::new_with_chunk_bits(10);
+            let mut rest = data.as_slice();
+            while let Some((chunk, tail)) = cdc.find_chunk(rest) {
+                chunks.push(chunk.to_vec());
+                rest = tail;
+            }
+            if !rest.is_empty() {
+                chunks.push(rest.to_vec());
+            }
+            chunks
+        };
+
+        let found: io::Result<Vec<Vec<u8>>> = Chunker::new_with_block_size(
+            data.as_slice(),
+            Gear::new_with_chunk_bits(10),
+            32,
+        ).collect();
+
+        assert_eq!(expected, found.unwrap());
+    }
+}